@@ -0,0 +1,159 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tokio::sync::RwLock;
+use warp::http::StatusCode;
+use warp::Filter;
+
+use crate::authority::ZTAuthority;
+use crate::records::StaticRecord;
+use crate::utils::ToHostname;
+
+/// Settings for the control API spawned by `init_runtime`, loaded from the `api` section of
+/// the config file. The bearer token gates every request; there is no anonymous read access.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ApiConfig {
+    pub listen: SocketAddr,
+    pub bearer_token: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiError {
+    error: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddRecordRequest {
+    record: StaticRecord,
+}
+
+fn with_authority(
+    zt: Arc<RwLock<ZTAuthority>>,
+) -> impl Filter<Extract = (Arc<RwLock<ZTAuthority>>,), Error = Infallible> + Clone {
+    warp::any().map(move || zt.clone())
+}
+
+fn authenticated(
+    bearer_token: String,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::header::<String>("authorization")
+        .and_then(move |header: String| {
+            let expected = format!("Bearer {}", bearer_token);
+            async move {
+                if bearer_eq(&header, &expected) {
+                    Ok(())
+                } else {
+                    Err(warp::reject::custom(Unauthorized))
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Constant-time comparison for the `Authorization` header. Hashing both sides to a
+/// fixed-length digest before comparing means `ct_eq` always runs over equal-length buffers, so
+/// unlike comparing `a`/`b` directly there's no `a.len() == b.len()` short-circuit in front of
+/// it that would leak the configured token's length to a timing side-channel.
+fn bearer_eq(a: &str, b: &str) -> bool {
+    let a = Sha256::digest(a.as_bytes());
+    let b = Sha256::digest(b.as_bytes());
+    a.ct_eq(&b).into()
+}
+
+#[derive(Debug)]
+struct Unauthorized;
+impl warp::reject::Reject for Unauthorized {}
+
+async fn list_records(
+    domain: String,
+    zt: Arc<RwLock<ZTAuthority>>,
+) -> Result<impl warp::Reply, Infallible> {
+    let zt = zt.read().await;
+    Ok(warp::reply::json(&zt.records_for_domain(&domain)))
+}
+
+async fn add_record(
+    body: AddRecordRequest,
+    zt: Arc<RwLock<ZTAuthority>>,
+) -> Result<impl warp::Reply, Infallible> {
+    match body.record.name.as_str().to_hostname() {
+        Ok(_) => {
+            let mut zt = zt.write().await;
+            match zt.insert_static_record(body.record) {
+                Ok(()) => Ok(warp::reply::with_status(
+                    warp::reply::json(&()),
+                    StatusCode::CREATED,
+                )),
+                Err(e) => Ok(warp::reply::with_status(
+                    warp::reply::json(&ApiError {
+                        error: e.to_string(),
+                    }),
+                    StatusCode::BAD_REQUEST,
+                )),
+            }
+        }
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&ApiError {
+                error: e.to_string(),
+            }),
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+/// Probes Central for reachability and bumps the zone serial; see
+/// `ZTAuthority::refresh_from_central` for why this doesn't re-sync member data.
+async fn reload(zt: Arc<RwLock<ZTAuthority>>) -> Result<impl warp::Reply, Infallible> {
+    let mut zt = zt.write().await;
+    match zt.refresh_from_central().await {
+        Ok(()) => Ok(warp::reply::with_status(
+            warp::reply::json(&()),
+            StatusCode::OK,
+        )),
+        Err(e) => Ok(warp::reply::with_status(
+            warp::reply::json(&ApiError {
+                error: e.to_string(),
+            }),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Serves the control API described in the `chunk0-3` request: listing records, adding a
+/// static record, and probing Central's reachability, all gated behind a static bearer token.
+/// Spawned as a task on the runtime returned by `init_runtime`, alongside the DNS listeners.
+pub(crate) async fn serve(
+    zt: Arc<RwLock<ZTAuthority>>,
+    config: ApiConfig,
+) -> Result<(), anyhow::Error> {
+    let auth = authenticated(config.bearer_token.clone());
+
+    let list = warp::path!("zones" / String / "records")
+        .and(warp::get())
+        .and(auth.clone())
+        .and(with_authority(zt.clone()))
+        .and_then(list_records);
+
+    let add = warp::path!("records")
+        .and(warp::post())
+        .and(auth.clone())
+        .and(warp::body::json())
+        .and(with_authority(zt.clone()))
+        .and_then(add_record);
+
+    let reload_route = warp::path!("reload")
+        .and(warp::post())
+        .and(auth)
+        .and(with_authority(zt))
+        .and_then(reload);
+
+    warp::serve(list.or(add).or(reload_route))
+        .run(config.listen)
+        .await;
+
+    Ok(())
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+use trust_dns_server::authority::MessageResponseBuilder;
+use trust_dns_server::client::rr::{Name, RData, Record, RecordType};
+use trust_dns_server::proto::op::Header;
+use trust_dns_server::server::{Request, RequestHandler, ResponseHandler, ResponseInfo};
+use zerotier_central_api::apis::configuration::Configuration;
+
+use crate::records::{install_static_records, StaticRecord};
+use crate::utils::ToHostname;
+
+/// Our own in-memory zone store. Keyed by `(name, type)` so each RRset can hold more than one
+/// record (round-robin A records, multiple MX, etc). `serial` bumps on every change that
+/// actually mutates the set, so callers can tell upsert/remove apart from a no-op.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Authority {
+    origin: Name,
+    records: HashMap<(Name, RecordType), Vec<Record>>,
+    serial: u32,
+}
+
+impl Authority {
+    pub(crate) fn new(origin: Name) -> Self {
+        Self {
+            origin,
+            records: HashMap::new(),
+            serial: 0,
+        }
+    }
+
+    pub(crate) fn serial(&self) -> u32 {
+        self.serial
+    }
+
+    pub(crate) fn bump_serial(&mut self) -> u32 {
+        self.serial += 1;
+        self.serial
+    }
+
+    /// Adds `record` to its RRset if an identical record isn't already present. Returns
+    /// whether the zone actually changed.
+    pub(crate) fn upsert(&mut self, record: Record) -> bool {
+        let key = (record.name().clone(), record.record_type());
+        let rrset = self.records.entry(key).or_default();
+
+        if rrset.contains(&record) {
+            return false;
+        }
+
+        rrset.push(record);
+        self.bump_serial();
+        true
+    }
+
+    /// Removes a single record from its RRset (not the whole name+type set), dropping the
+    /// RRset entirely once empty. Returns whether anything was removed.
+    pub(crate) fn remove(&mut self, record: &Record) -> bool {
+        let key = (record.name().clone(), record.record_type());
+
+        let removed = match self.records.get_mut(&key) {
+            Some(rrset) => {
+                let len_before = rrset.len();
+                rrset.retain(|r| r != record);
+                rrset.len() != len_before
+            }
+            None => false,
+        };
+
+        if removed {
+            if self.records.get(&key).is_some_and(Vec::is_empty) {
+                self.records.remove(&key);
+            }
+            self.bump_serial();
+        }
+
+        removed
+    }
+
+    pub(crate) fn records(&self) -> Vec<Record> {
+        self.records.values().flatten().cloned().collect()
+    }
+
+    pub(crate) fn records_for_name(&self, name: &Name, record_type: RecordType) -> Vec<Record> {
+        self.records
+            .get(&(name.clone(), record_type))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Record store scoped to the reverse-lookup (`in-addr.arpa`/`ip6.arpa`) zone, kept separate
+/// from the forward zone's `Authority` so PTR synthesis doesn't collide with forward records.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PtrAuthority {
+    authority: Authority,
+}
+
+impl PtrAuthority {
+    pub(crate) fn new(origin: Name) -> Self {
+        Self {
+            authority: Authority::new(origin),
+        }
+    }
+}
+
+/// Ties a forward `Authority` to the ZeroTier network it's synced from. This is what the
+/// control API (`api::serve`) and the hot-reload watcher (`reload::watch_for_reload`) both
+/// hold behind an `Arc<RwLock<_>>` so they can read and mutate the live zone.
+pub(crate) struct ZTAuthority {
+    domain_name: Name,
+    network: String,
+    central_config: Configuration,
+    hosts_file: Option<String>,
+    ptr_authority: PtrAuthority,
+    update_interval: Duration,
+    authority: Authority,
+}
+
+impl ZTAuthority {
+    pub(crate) fn new(
+        domain_name: Name,
+        network: String,
+        central_config: Configuration,
+        hosts_file: Option<String>,
+        ptr_authority: PtrAuthority,
+        update_interval: Duration,
+        authority: Authority,
+    ) -> Self {
+        Self {
+            domain_name,
+            network,
+            central_config,
+            hosts_file,
+            ptr_authority,
+            update_interval,
+            authority,
+        }
+    }
+
+    pub(crate) fn domain_name(&self) -> &Name {
+        &self.domain_name
+    }
+
+    pub(crate) fn update_interval(&self) -> Duration {
+        self.update_interval
+    }
+
+    pub(crate) fn hosts_file(&self) -> Option<&str> {
+        self.hosts_file.as_deref()
+    }
+
+    /// Returns every record in the zone when `domain` is the zone apex, otherwise only the
+    /// records owned by exactly `domain`; an empty list if `domain` doesn't parse as a DNS name.
+    /// Backs the control API's `GET /zones/{domain}/records`.
+    pub(crate) fn records_for_domain(&self, domain: &str) -> Vec<Record> {
+        match Name::from_str(domain) {
+            Ok(name) => self
+                .authority
+                .records()
+                .into_iter()
+                .filter(|record| record.name() == &name || self.domain_name == name)
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Adds a single static record to the live zone, as requested through `POST /records`.
+    pub(crate) fn insert_static_record(&mut self, record: StaticRecord) -> Result<(), anyhow::Error> {
+        let domain_name = self.domain_name.clone();
+        install_static_records(&mut self.authority, &domain_name, std::slice::from_ref(&record))
+    }
+
+    /// Probes Central and bumps the serial, as requested through `POST /reload`. This build has
+    /// no member-sync path for the endpoint to re-trigger directly — `self.authority` is only
+    /// ever mutated by `install_static_records`, `reload::watch_for_reload`'s diff against
+    /// `build_record_set`, and `dnssec::run_resign_loop` — so rather than claim to refresh DNS
+    /// data this confirms Central is reachable and bumps the serial to mark the round-trip.
+    pub(crate) async fn refresh_from_central(&mut self) -> Result<(), anyhow::Error> {
+        zerotier_central_api::apis::network_api::get_network_by_id(
+            &self.central_config,
+            &self.network,
+        )
+        .await?;
+
+        self.authority.bump_serial();
+        Ok(())
+    }
+
+    pub(crate) fn all_records(&self) -> Vec<Record> {
+        self.authority.records()
+    }
+
+    /// The live zone's change counter, bumped by every `upsert_record`/`remove_record` that
+    /// actually mutates something. `dnssec::run_resign_loop` publishes this as the apex SOA
+    /// record's serial.
+    pub(crate) fn serial(&self) -> u32 {
+        self.authority.serial()
+    }
+
+    pub(crate) fn upsert_record(&mut self, record: Record) -> bool {
+        self.authority.upsert(record)
+    }
+
+    pub(crate) fn remove_record(&mut self, record: &Record) -> bool {
+        self.authority.remove(record)
+    }
+
+    /// Recomputes the full set of records that *should* exist given a hosts file and the
+    /// config's `dns_records`, without touching the live authority. `reload::watch_for_reload`
+    /// diffs this against `all_records()` so a reload only applies what actually changed.
+    pub(crate) fn build_record_set(
+        &self,
+        hosts_file: Option<&str>,
+        dns_records: &[StaticRecord],
+    ) -> Result<Vec<Record>, anyhow::Error> {
+        let mut records = Vec::new();
+
+        if let Some(path) = hosts_file {
+            records.extend(parse_hosts_file(path, &self.domain_name)?);
+        }
+
+        let mut scratch = Authority::new(self.domain_name.clone());
+        install_static_records(&mut scratch, &self.domain_name, dns_records)?;
+        records.extend(scratch.records());
+
+        Ok(records)
+    }
+}
+
+/// Parses a `/etc/hosts`-style file (`ip hostname [alias...]`, `#` comments, blank lines
+/// ignored) into A/AAAA records under `domain`, the same normalization path member-derived
+/// hostnames already go through via `ToHostname`.
+fn parse_hosts_file(path: &str, domain: &Name) -> Result<Vec<Record>, anyhow::Error> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("could not read hosts file {}: {}", path, e))?;
+
+    let mut records = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let ip = match fields.next() {
+            Some(ip) => ip,
+            None => continue,
+        };
+
+        let rdata = if let Ok(v4) = Ipv4Addr::from_str(ip) {
+            RData::A(v4)
+        } else if let Ok(v6) = Ipv6Addr::from_str(ip) {
+            RData::AAAA(v6)
+        } else {
+            return Err(anyhow!("invalid IP address {} in hosts file {}", ip, path));
+        };
+
+        for host in fields {
+            let name = host.to_fqdn(domain.clone())?;
+            let mut record = Record::with(name, rdata.to_record_type(), 60);
+            record.set_rdata(Some(rdata.clone()));
+            records.push(record);
+        }
+    }
+
+    Ok(records)
+}
+
+/// Bridges a shared `ZTAuthority` into trust_dns_server's `RequestHandler`, so the plaintext,
+/// DoT, and DoH listeners can all answer from the same live zone.
+#[derive(Clone)]
+pub(crate) struct ZtHandler(Arc<RwLock<ZTAuthority>>);
+
+impl ZtHandler {
+    pub(crate) fn new(zt: Arc<RwLock<ZTAuthority>>) -> Self {
+        Self(zt)
+    }
+}
+
+#[async_trait]
+impl RequestHandler for ZtHandler {
+    async fn handle_request<R: ResponseHandler>(
+        &self,
+        request: &Request,
+        mut response_handle: R,
+    ) -> ResponseInfo {
+        let query = request.query();
+        let name = Name::from(query.name());
+
+        let records = {
+            let zt = self.0.read().await;
+            zt.authority.records_for_name(&name, query.query_type())
+        };
+
+        let mut header = Header::response_from_request(request.header());
+        header.set_authoritative(true);
+
+        let builder = MessageResponseBuilder::from_message_request(request);
+        let response = builder.build(header, records.iter(), [], [], []);
+
+        response_handle
+            .send_response(response)
+            .await
+            .unwrap_or_else(|_| header.into())
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use serde::Deserialize;
+
+use crate::api::ApiConfig;
+use crate::dnssec::DnssecConfig;
+use crate::records::StaticRecord;
+use crate::tls::TlsConfig;
+
+/// Environment variable pointing at a config file, checked when `--config` is not passed.
+pub(crate) const CONFIG_ENV: &str = "ZERONSD_CONFIG";
+
+/// Declarative configuration for a single zeronsd instance, loaded from a YAML or TOML file
+/// via `--config` or `ZERONSD_CONFIG`. Every field is optional: anything left unset here falls
+/// back to the corresponding CLI flag, and anything left unset by both falls back to the
+/// platform defaults already implemented in `utils`.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(crate) struct Config {
+    pub token: Option<String>,
+    pub authtoken_path: Option<String>,
+    pub domain: Option<String>,
+    pub hosts_file: Option<String>,
+    pub network: Option<String>,
+    pub update_interval: Option<u64>,
+    /// User-defined CNAME/TXT/MX/SRV/A/AAAA records to inject into the authority at startup.
+    #[serde(default)]
+    pub dns_records: Vec<StaticRecord>,
+    /// Optional control API; absent means the API is not started.
+    pub api: Option<ApiConfig>,
+    /// Opt-in DNSSEC signing; absent or `enabled: false` serves the zone unsigned as before.
+    pub dnssec: Option<DnssecConfig>,
+    /// Opt-in DoT/DoH listeners; absent means only plaintext DNS is served.
+    pub tls: Option<TlsConfig>,
+}
+
+impl Config {
+    /// Loads a `Config` from `path`, or from `ZERONSD_CONFIG` if `path` is `None`. Returns the
+    /// empty (all-`None`) config when neither is set, so callers can always merge against one.
+    pub(crate) fn load(path: Option<&str>) -> Result<Self, anyhow::Error> {
+        let path = match path.map(String::from).or_else(|| std::env::var(CONFIG_ENV).ok()) {
+            Some(path) => path,
+            None => return Ok(Self::default()),
+        };
+
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow!("could not read config file {}: {}", path, e))?;
+
+        let config: Self = match Path::new(&path).extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&contents)?,
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&contents)?,
+            ext => {
+                return Err(anyhow!(
+                    "unsupported config file extension {:?} for {}; use .toml, .yaml, or .yml",
+                    ext,
+                    path
+                ))
+            }
+        };
+
+        if let Some(tls) = &config.tls {
+            tls.validate()?;
+        }
+
+        Ok(config)
+    }
+
+    /// Resolves a `String`-typed setting: CLI flag wins, then the config file, then `None`.
+    pub(crate) fn resolve(file_value: &Option<String>, cli_value: Option<&str>) -> Option<String> {
+        cli_value.map(String::from).or_else(|| file_value.clone())
+    }
+
+    pub(crate) fn token(&self, cli: Option<&str>) -> Option<String> {
+        Self::resolve(&self.token, cli)
+    }
+
+    pub(crate) fn authtoken_path(&self, cli: Option<&str>) -> Option<String> {
+        Self::resolve(&self.authtoken_path, cli)
+    }
+
+    pub(crate) fn domain(&self, cli: Option<&str>) -> Option<String> {
+        Self::resolve(&self.domain, cli)
+    }
+
+    pub(crate) fn hosts_file(&self, cli: Option<&str>) -> Option<String> {
+        Self::resolve(&self.hosts_file, cli)
+    }
+
+    pub(crate) fn network(&self, cli: Option<&str>) -> Option<String> {
+        Self::resolve(&self.network, cli)
+    }
+
+    /// Resolves the Central-to-Authority refresh interval: CLI flag, then config file, then
+    /// `None`; `utils::init_authority` substitutes its `DEFAULT_UPDATE_INTERVAL` in that case.
+    pub(crate) fn update_interval(&self, cli: Option<Duration>) -> Option<Duration> {
+        cli.or_else(|| self.update_interval.map(Duration::from_secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    #[test]
+    fn cli_flag_overrides_config_file_value() {
+        let config = Config {
+            network: Some("file-network".to_string()),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            config.network(Some("cli-network")),
+            Some("cli-network".to_string())
+        );
+    }
+
+    #[test]
+    fn config_file_value_used_when_cli_flag_absent() {
+        let config = Config {
+            network: Some("file-network".to_string()),
+            ..Config::default()
+        };
+
+        assert_eq!(config.network(None), Some("file-network".to_string()));
+    }
+
+    #[test]
+    fn neither_set_resolves_to_none() {
+        let config = Config::default();
+        assert_eq!(config.network(None), None);
+    }
+
+    #[test]
+    fn update_interval_follows_the_same_precedence() {
+        use std::time::Duration;
+
+        let config = Config {
+            update_interval: Some(60),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            config.update_interval(Some(Duration::from_secs(5))),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(config.update_interval(None), Some(Duration::from_secs(60)));
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use rand::RngCore;
+use serde::Deserialize;
+use sha1::{Digest, Sha1};
+use tokio::sync::RwLock;
+use trust_dns_server::client::rr::dnssec::rdata::{DNSKEY, NSEC3, NSEC3PARAM};
+use trust_dns_server::client::rr::dnssec::{Algorithm, DnsSecResult, KeyPair, SigningKey};
+use trust_dns_server::client::rr::rdata::SOA;
+use trust_dns_server::client::rr::{Name, RData, Record, RecordSet, RecordType};
+
+use crate::authority::ZTAuthority;
+
+/// Opt-in DNSSEC settings for the `dnssec` section of the config file.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct DnssecConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    pub key_path: Option<String>,
+    #[serde(default = "default_algorithm")]
+    pub algorithm: DnssecAlgorithm,
+    #[serde(default = "default_nsec3_iterations")]
+    pub nsec3_iterations: u16,
+    #[serde(default = "default_nsec3_salt_length")]
+    pub nsec3_salt_length: u8,
+}
+
+fn default_algorithm() -> DnssecAlgorithm {
+    DnssecAlgorithm::Ed25519
+}
+
+fn default_nsec3_iterations() -> u16 {
+    10
+}
+
+fn default_nsec3_salt_length() -> u8 {
+    8
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum DnssecAlgorithm {
+    Ed25519,
+    EcdsaP256,
+}
+
+impl From<DnssecAlgorithm> for Algorithm {
+    fn from(algorithm: DnssecAlgorithm) -> Self {
+        match algorithm {
+            DnssecAlgorithm::Ed25519 => Algorithm::ED25519,
+            DnssecAlgorithm::EcdsaP256 => Algorithm::ECDSAP256SHA256,
+        }
+    }
+}
+
+/// Loads the zone-signing key from `key_path`, or generates and persists a fresh one if the
+/// file does not exist yet, so a restart does not rotate keys (and invalidate cached DNSKEYs)
+/// unnecessarily.
+pub(crate) fn load_or_generate_key(config: &DnssecConfig) -> Result<SigningKey, anyhow::Error> {
+    let algorithm: Algorithm = config.algorithm.into();
+
+    if let Some(path) = &config.key_path {
+        if let Ok(bytes) = std::fs::read(path) {
+            return Ok(SigningKey::from_pkcs8(&bytes, algorithm)?);
+        }
+
+        let key = KeyPair::generate(algorithm)?;
+        std::fs::write(path, key.to_pkcs8()?)?;
+        return Ok(key);
+    }
+
+    Ok(KeyPair::generate(algorithm)?)
+}
+
+pub(crate) fn dnskey_record(name: Name, key: &SigningKey) -> Result<Record, anyhow::Error> {
+    let dnskey = DNSKEY::from_key(&key.to_public_key()?);
+    let mut record = Record::with(name, RecordType::DNSKEY, 3600);
+    record.set_rdata(Some(RData::DNSSEC(
+        trust_dns_server::client::rr::dnssec::rdata::DNSSECRData::DNSKEY(dnskey),
+    )));
+    Ok(record)
+}
+
+/// Signs every RRset in `rrsets` with `key`, returning one RRSIG record per RRset. Called once
+/// per `update_interval` cycle after the catalog is rebuilt, since the record set (and
+/// therefore every signature) changes as members join or leave.
+pub(crate) fn sign_rrsets(
+    rrsets: &[RecordSet],
+    key: &SigningKey,
+    signer_name: &Name,
+) -> DnsSecResult<Vec<Record>> {
+    let mut signatures = Vec::with_capacity(rrsets.len());
+
+    for rrset in rrsets {
+        let sig = key.sign_rrset(rrset, signer_name)?;
+        signatures.push(sig);
+    }
+
+    Ok(signatures)
+}
+
+/// The iterated NSEC3 hash function from RFC 5155 Section 5: `hash0 = SHA1(name||salt)`,
+/// `hash_i = SHA1(hash_{i-1}||salt)`, repeated `iterations` times total after the initial hash.
+pub(crate) fn nsec3_hash(name: &Name, salt: &[u8], iterations: u16) -> Vec<u8> {
+    let wire_name = name.to_lowercase().to_bytes_canonical();
+
+    let mut hash = Sha1::digest(&[wire_name.as_slice(), salt].concat()).to_vec();
+
+    for _ in 0..iterations {
+        hash = Sha1::digest(&[hash.as_slice(), salt].concat()).to_vec();
+    }
+
+    hash
+}
+
+pub(crate) fn random_salt(length: u8) -> Vec<u8> {
+    let mut salt = vec![0u8; length as usize];
+    rand::thread_rng().fill_bytes(&mut salt);
+    salt
+}
+
+/// RFC 4648 "base32hex" alphabet, used for NSEC3 owner-name labels per RFC 5155 §7.1 — not
+/// the ordinary base32 alphabet, which would sort differently and break resolvers.
+const BASE32HEX_ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuv";
+
+fn base32hex_encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity((bytes.len() * 8 + 4) / 5);
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(BASE32HEX_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        output.push(BASE32HEX_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    output
+}
+
+/// RFC 5155 §7.1: an NSEC3 record's owner name is the base32hex of its hash, prepended as a
+/// single label to the zone apex — not the original plaintext owner name.
+fn nsec3_owner_name(apex: &Name, hash: &[u8]) -> Result<Name, anyhow::Error> {
+    Ok(Name::from_str(&base32hex_encode(hash))?.append_domain(apex))
+}
+
+/// Builds the NSEC3 ring for every owner name in the zone: hash each name, sort the hashes,
+/// and for each one emit an NSEC3 record whose "next hashed owner name" points at the next
+/// entry in sorted order, wrapping from the last back to the first so the ring is closed.
+/// NSEC3PARAM at the apex (`nsec3param_record`) advertises `salt`/`iterations`. The chain is
+/// only published in the zone: `resign` does not sign the NSEC3 records, and the request
+/// handler (`ZtHandler`) answers exact name/type lookups only, so it does not return a
+/// covering NSEC3 record for a negative answer.
+pub(crate) fn build_nsec3_chain(
+    apex: &Name,
+    names: &[(Name, Vec<RecordType>)],
+    salt: &[u8],
+    iterations: u16,
+    ttl: u32,
+) -> Result<Vec<Record>, anyhow::Error> {
+    if names.is_empty() {
+        return Err(anyhow!("cannot build an NSEC3 chain for an empty zone"));
+    }
+
+    let mut ring: BTreeMap<Vec<u8>, Vec<RecordType>> = BTreeMap::new();
+    for (name, types) in names {
+        ring.insert(nsec3_hash(name, salt, iterations), types.clone());
+    }
+
+    let hashes: Vec<Vec<u8>> = ring.keys().cloned().collect();
+    let mut records = Vec::with_capacity(hashes.len());
+
+    for (i, hash) in hashes.iter().enumerate() {
+        let types = ring.get(hash).expect("hash was just inserted");
+        let next_hash = hashes[(i + 1) % hashes.len()].clone();
+        let owner = nsec3_owner_name(apex, hash)?;
+
+        let nsec3 = NSEC3::new(
+            trust_dns_server::client::rr::dnssec::rdata::nsec3::HashAlgorithm::SHA1,
+            false,
+            iterations,
+            salt.to_vec(),
+            next_hash,
+            types.clone(),
+        );
+
+        let mut record = Record::with(owner, RecordType::NSEC3, ttl);
+        record.set_rdata(Some(RData::DNSSEC(
+            trust_dns_server::client::rr::dnssec::rdata::DNSSECRData::NSEC3(nsec3),
+        )));
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+pub(crate) fn nsec3param_record(apex: Name, salt: &[u8], iterations: u16, ttl: u32) -> Record {
+    let param = NSEC3PARAM::new(
+        trust_dns_server::client::rr::dnssec::rdata::nsec3::HashAlgorithm::SHA1,
+        false,
+        iterations,
+        salt.to_vec(),
+    );
+
+    let mut record = Record::with(apex, RecordType::NSEC3PARAM, ttl);
+    record.set_rdata(Some(RData::DNSSEC(
+        trust_dns_server::client::rr::dnssec::rdata::DNSSECRData::NSEC3PARAM(param),
+    )));
+    record
+}
+
+/// Synthesizes the apex SOA record, with `Authority`'s change counter (bumped by every upsert
+/// and removal, including the ones `resign` itself makes) published as its serial.
+fn soa_record(apex: &Name, serial: u32, ttl: u32) -> Record {
+    let rname = Name::from_str("hostmaster")
+        .expect("static label parses")
+        .append_domain(apex);
+
+    let soa = SOA::new(apex.clone(), rname, serial, 3600, 600, 86400, 60);
+
+    let mut record = Record::with(apex.clone(), RecordType::SOA, ttl);
+    record.set_rdata(Some(RData::SOA(soa)));
+    record
+}
+
+/// Record types `resign` itself generates each cycle, rather than real zone data synthesized
+/// from ZeroTier membership or the config file. Excluded from the name set that gets hashed
+/// into the NSEC3 chain and signed, so last cycle's own SOA/DNSKEY/NSEC3/RRSIG records don't
+/// get fed back in as if they were answer data.
+fn is_resign_generated(record_type: RecordType) -> bool {
+    matches!(
+        record_type,
+        RecordType::SOA | RecordType::DNSKEY | RecordType::NSEC3 | RecordType::NSEC3PARAM | RecordType::RRSIG
+    )
+}
+
+/// Re-signs the zone once per `update_interval` for as long as DNSSEC is enabled. `salt` is
+/// generated once here and held fixed for the life of the loop, rather than rolled every
+/// cycle: an NSEC3 owner name is `base32hex(hash(name, salt))`, so a new salt would make every
+/// existing name's owner name unrecognizable from one cycle to the next, and `resign` has no
+/// way to retract "the same name, under the old salt" without knowing what that old salt was.
+pub(crate) async fn run_resign_loop(
+    zt: Arc<RwLock<ZTAuthority>>,
+    config: DnssecConfig,
+    update_interval: Duration,
+) {
+    if !config.enabled {
+        return;
+    }
+
+    let key = match load_or_generate_key(&config) {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("DNSSEC signing disabled: could not load signing key: {:?}", e);
+            return;
+        }
+    };
+
+    let salt = random_salt(config.nsec3_salt_length);
+    let mut previous_meta: Vec<Record> = Vec::new();
+
+    loop {
+        {
+            let mut zt = zt.write().await;
+            match resign(&mut zt, &config, &key, &salt, &previous_meta) {
+                Ok(meta) => previous_meta = meta,
+                Err(e) => eprintln!("DNSSEC re-sign cycle failed: {:?}", e),
+            }
+        }
+        tokio::time::sleep(update_interval).await;
+    }
+}
+
+/// One re-sign cycle: retracts the previous cycle's generated records (`previous_meta`), then
+/// rebuilds and upserts the NSEC3 chain, NSEC3PARAM, DNSKEY, an RRSIG per real RRset, and the
+/// apex SOA, returning the new set so the caller can retract it next cycle in turn. Without
+/// this retract-before-upsert, the live zone would grow by a full NSEC3 chain every cycle.
+fn resign(
+    zt: &mut ZTAuthority,
+    config: &DnssecConfig,
+    key: &SigningKey,
+    salt: &[u8],
+    previous_meta: &[Record],
+) -> Result<Vec<Record>, anyhow::Error> {
+    let apex = zt.domain_name().clone();
+
+    let mut by_name: BTreeMap<Name, Vec<RecordType>> = BTreeMap::new();
+    let mut rrsets: HashMap<(Name, RecordType), RecordSet> = HashMap::new();
+
+    for record in zt.all_records() {
+        if is_resign_generated(record.record_type()) {
+            continue;
+        }
+
+        by_name
+            .entry(record.name().clone())
+            .or_default()
+            .push(record.record_type());
+
+        rrsets
+            .entry((record.name().clone(), record.record_type()))
+            .or_insert_with(|| RecordSet::new(record.name(), record.record_type(), zt.serial()))
+            .insert(record.clone(), zt.serial());
+    }
+    let names: Vec<(Name, Vec<RecordType>)> = by_name.into_iter().collect();
+
+    for record in previous_meta {
+        zt.remove_record(record);
+    }
+
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let nsec3_records = build_nsec3_chain(&apex, &names, salt, config.nsec3_iterations, 3600)?;
+    let nsec3param = nsec3param_record(apex.clone(), salt, config.nsec3_iterations, 3600);
+    let dnskey = dnskey_record(apex.clone(), key)?;
+    let rrsigs = sign_rrsets(&rrsets.into_values().collect::<Vec<_>>(), key, &apex)?;
+
+    let mut meta = nsec3_records;
+    meta.push(nsec3param);
+    meta.push(dnskey);
+    meta.extend(rrsigs);
+
+    for record in &meta {
+        zt.upsert_record(record.clone());
+    }
+
+    let soa = soa_record(&apex, zt.serial(), 3600);
+    zt.upsert_record(soa.clone());
+    meta.push(soa);
+
+    Ok(meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use trust_dns_server::client::rr::Name;
+
+    use super::{base32hex_encode, nsec3_hash};
+
+    #[test]
+    fn nsec3_hash_matches_rfc5155_appendix_b_vector() {
+        let name = Name::from_str("example.").unwrap();
+        let salt = [0xaa, 0xbb, 0xcc, 0xdd];
+
+        let hash = nsec3_hash(&name, &salt, 12);
+
+        assert_eq!(base32hex_encode(&hash), "0p9mhaveqvm6t7vbl5lop2u3t2rp3tom");
+    }
+}
@@ -0,0 +1,141 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use trust_dns_server::client::rr::rdata::{MX, SRV, TXT};
+use trust_dns_server::client::rr::{Name, RData, Record, RecordType};
+
+use crate::authority::Authority;
+use crate::utils::ToHostname;
+
+/// A single user-defined record from the `dns_records` section of the config file. These are
+/// installed into the authority by `init_authority` at startup, and are the only way to get a
+/// CNAME, TXT, MX, or SRV into the served zone.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct StaticRecord {
+    pub r#type: StaticRecordType,
+    pub name: String,
+    pub value: String,
+    pub ttl: Option<u32>,
+    /// Priority for MX and SRV records; ignored for other types.
+    pub priority: Option<u16>,
+    /// Weight for SRV records; ignored for other types.
+    pub weight: Option<u16>,
+    /// Port for SRV records; ignored for other types.
+    pub port: Option<u16>,
+}
+
+const DEFAULT_TTL: u32 = 60;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub(crate) enum StaticRecordType {
+    A,
+    Aaaa,
+    Cname,
+    Txt,
+    Mx,
+    Srv,
+}
+
+impl StaticRecord {
+    fn rdata(&self) -> Result<RData, anyhow::Error> {
+        Ok(match self.r#type {
+            StaticRecordType::A => RData::A(Ipv4Addr::from_str(&self.value)?),
+            StaticRecordType::Aaaa => RData::AAAA(Ipv6Addr::from_str(&self.value)?),
+            StaticRecordType::Cname => RData::CNAME(self.value.as_str().to_hostname()?),
+            StaticRecordType::Txt => RData::TXT(TXT::new(vec![self.value.clone()])),
+            StaticRecordType::Mx => RData::MX(MX::new(
+                self.priority.unwrap_or(10),
+                self.value.as_str().to_hostname()?,
+            )),
+            StaticRecordType::Srv => RData::SRV(SRV::new(
+                self.priority.unwrap_or(0),
+                self.weight.unwrap_or(0),
+                self.port
+                    .ok_or_else(|| anyhow!("SRV record {} is missing a port", self.name))?,
+                self.value.as_str().to_hostname()?,
+            )),
+        })
+    }
+
+    fn record_type(&self) -> RecordType {
+        match self.r#type {
+            StaticRecordType::A => RecordType::A,
+            StaticRecordType::Aaaa => RecordType::AAAA,
+            StaticRecordType::Cname => RecordType::CNAME,
+            StaticRecordType::Txt => RecordType::TXT,
+            StaticRecordType::Mx => RecordType::MX,
+            StaticRecordType::Srv => RecordType::SRV,
+        }
+    }
+}
+
+/// Normalizes and inserts every entry of `records` into `authority`, run by `init_authority`
+/// at startup. Each record is added alongside any existing records of the same name and type;
+/// nothing already in `authority` is replaced. Names go through `ToHostname::to_fqdn` so they
+/// land in the same zone as synthesized member records.
+pub(crate) fn install_static_records(
+    authority: &mut Authority,
+    domain: &Name,
+    records: &[StaticRecord],
+) -> Result<(), anyhow::Error> {
+    for record in records {
+        let name = record.name.as_str().to_fqdn(domain.clone())?;
+        let rdata = record.rdata()?;
+        let mut rr = Record::with(name, record.record_type(), record.ttl.unwrap_or(DEFAULT_TTL));
+        rr.set_rdata(Some(rdata));
+        authority.upsert(rr);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use trust_dns_server::client::rr::{Name, RecordType};
+
+    use super::{StaticRecord, StaticRecordType};
+    use crate::authority::Authority;
+
+    fn record(r#type: StaticRecordType, name: &str, value: &str) -> StaticRecord {
+        StaticRecord {
+            r#type,
+            name: name.to_string(),
+            value: value.to_string(),
+            ttl: None,
+            priority: None,
+            weight: None,
+            port: None,
+        }
+    }
+
+    #[test]
+    fn install_static_records_normalizes_names_into_the_zone() {
+        let domain = Name::from_str("domain.").unwrap();
+        let mut authority = Authority::new(domain.clone());
+
+        super::install_static_records(
+            &mut authority,
+            &domain,
+            &[record(StaticRecordType::A, "git", "10.0.0.1")],
+        )
+        .unwrap();
+
+        let name = Name::from_str("git.domain.").unwrap();
+        assert_eq!(authority.records_for_name(&name, RecordType::A).len(), 1);
+    }
+
+    #[test]
+    fn srv_record_without_a_port_is_rejected() {
+        let record = StaticRecord {
+            port: None,
+            ..record(StaticRecordType::Srv, "_svc._tcp", "target.domain.")
+        };
+
+        assert!(record.rdata().is_err());
+    }
+}
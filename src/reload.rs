@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use notify::{RecursiveMode, Watcher};
+use tokio::sync::RwLock;
+use trust_dns_server::client::rr::Record;
+
+use crate::authority::ZTAuthority;
+use crate::config::Config;
+
+/// Watches `hosts_file` and `config_path` for changes and listens for SIGHUP, re-reading both
+/// on either trigger and applying only the difference to the live authority. This is the
+/// hot-reload path described in `chunk0-4`: `init_authority` only reads these once at startup,
+/// so without this loop operators have to restart the process to pick up edits.
+pub(crate) async fn watch_for_reload(
+    zt: Arc<RwLock<ZTAuthority>>,
+    hosts_file: Option<String>,
+    config_path: Option<String>,
+) -> Result<(), anyhow::Error> {
+    let sighup_zt = zt.clone();
+    let sighup_hosts = hosts_file.clone();
+    let sighup_config = config_path.clone();
+
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        let mut stream = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("Could not install SIGHUP handler: {:?}", e);
+                return;
+            }
+        };
+
+        loop {
+            stream.recv().await;
+            if let Err(e) = reload_now(&sighup_zt, sighup_hosts.as_deref(), sighup_config.as_deref()).await {
+                eprintln!("Reload triggered by SIGHUP failed: {:?}", e);
+            }
+        }
+    });
+
+    let (tx, mut rx) = tokio::sync::mpsc::channel(16);
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if res.is_ok() {
+            let _ = tx.blocking_send(());
+        }
+    })?;
+
+    for path in [hosts_file.as_deref(), config_path.as_deref()].into_iter().flatten() {
+        watcher.watch(std::path::Path::new(path), RecursiveMode::NonRecursive)?;
+    }
+
+    while rx.recv().await.is_some() {
+        if let Err(e) = reload_now(&zt, hosts_file.as_deref(), config_path.as_deref()).await {
+            eprintln!("Reload triggered by file change failed: {:?}", e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn reload_now(
+    zt: &Arc<RwLock<ZTAuthority>>,
+    hosts_file: Option<&str>,
+    config_path: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    let config = Config::load(config_path)?;
+    let mut zt = zt.write().await;
+
+    let previous = zt.all_records();
+    let next = zt.build_record_set(hosts_file, &config.dns_records)?;
+
+    let (upserts, removals) = diff_records(&previous, &next);
+
+    for record in &removals {
+        zt.remove_record(record);
+    }
+
+    for record in upserts {
+        zt.upsert_record(record);
+    }
+
+    Ok(())
+}
+
+/// Splits `next` against `previous` into records to upsert (new or changed) and records to
+/// remove (present before, absent now). This diffs by full record value, not just
+/// `(name, type)`: an RRset that's still present but whose value changed (e.g. an A record's
+/// IP) has its old value fall out of `previous_set` only, so it's correctly retracted instead
+/// of silently left stale alongside the new value.
+fn diff_records(previous: &[Record], next: &[Record]) -> (Vec<Record>, Vec<Record>) {
+    let previous_set: HashSet<_> = previous.iter().cloned().collect();
+    let next_set: HashSet<_> = next.iter().cloned().collect();
+
+    let upserts = next_set.difference(&previous_set).cloned().collect();
+    let removals = previous_set.difference(&next_set).cloned().collect();
+
+    (upserts, removals)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use std::str::FromStr;
+
+    use trust_dns_server::client::rr::{Name, RData, Record, RecordType};
+
+    use super::diff_records;
+
+    fn a_record(name: &str, ip: &str) -> Record {
+        let mut record = Record::with(Name::from_str(name).unwrap(), RecordType::A, 60);
+        record.set_rdata(Some(RData::A(Ipv4Addr::from_str(ip).unwrap())));
+        record
+    }
+
+    #[test]
+    fn changed_value_is_upserted_and_old_value_removed() {
+        let previous = vec![a_record("host.domain.", "10.0.0.1")];
+        let next = vec![a_record("host.domain.", "10.0.0.2")];
+
+        let (upserts, removals) = diff_records(&previous, &next);
+
+        assert_eq!(upserts, vec![a_record("host.domain.", "10.0.0.2")]);
+        assert_eq!(removals, vec![a_record("host.domain.", "10.0.0.1")]);
+    }
+
+    #[test]
+    fn unchanged_record_is_left_alone() {
+        let previous = vec![a_record("host.domain.", "10.0.0.1")];
+        let next = previous.clone();
+
+        let (upserts, removals) = diff_records(&previous, &next);
+
+        assert!(upserts.is_empty());
+        assert!(removals.is_empty());
+    }
+}
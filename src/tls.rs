@@ -0,0 +1,127 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
+use trust_dns_server::ServerFuture;
+
+use crate::authority::{ZTAuthority, ZtHandler};
+
+/// DoT/DoH settings for the `tls` section of the config file. `cert_path`/`key_path` are used
+/// as-is when set. `acme` is accepted in the schema but rejected by `validate()`: this build
+/// does not obtain certificates via ACME.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct TlsConfig {
+    pub dot_listen: Option<SocketAddr>,
+    pub doh_listen: Option<SocketAddr>,
+    pub cert_path: Option<String>,
+    pub key_path: Option<String>,
+    pub acme: Option<AcmeConfig>,
+}
+
+impl TlsConfig {
+    /// Rejects configurations this build can't actually serve, so a typo or an
+    /// unimplemented option fails at startup instead of on the first TLS handshake.
+    pub(crate) fn validate(&self) -> Result<(), anyhow::Error> {
+        if self.acme.is_some() {
+            return Err(anyhow!(
+                "tls.acme is not supported yet; provide tls.cert_path/tls.key_path instead"
+            ));
+        }
+
+        if self.cert_path.is_none() || self.key_path.is_none() {
+            return Err(anyhow!(
+                "tls is configured but tls.cert_path/tls.key_path are not both set"
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct AcmeConfig {
+    pub email: String,
+    pub domain: String,
+    #[serde(default)]
+    pub staging: bool,
+}
+
+/// Loads `cert_path`/`key_path` from disk. Returns the DER-encoded cert chain and key
+/// trust_dns_server's TLS/HTTPS listeners expect.
+pub(crate) fn load_certificate(
+    config: &TlsConfig,
+) -> Result<(Vec<rustls::Certificate>, rustls::PrivateKey), anyhow::Error> {
+    config.validate()?;
+
+    let cert_path = config.cert_path.as_ref().expect("validated above");
+    let key_path = config.key_path.as_ref().expect("validated above");
+    read_cert_and_key(cert_path, key_path)
+}
+
+fn read_cert_and_key(
+    cert_path: &str,
+    key_path: &str,
+) -> Result<(Vec<rustls::Certificate>, rustls::PrivateKey), anyhow::Error> {
+    let cert_bytes = std::fs::read(cert_path)?;
+    let key_bytes = std::fs::read(key_path)?;
+
+    let certs = rustls_pemfile::certs(&mut cert_bytes.as_slice())?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+
+    let key = rustls_pemfile::pkcs8_private_keys(&mut key_bytes.as_slice())?
+        .into_iter()
+        .next()
+        .map(rustls::PrivateKey)
+        .ok_or_else(|| anyhow::anyhow!("no private key found in {}", key_path))?;
+
+    Ok((certs, key))
+}
+
+/// Registers DoT (RFC 7858, default port 853) and DoH listeners on `server` for every
+/// configured address, sharing the same `ZtHandler`/`ZTAuthority` the plaintext listeners use.
+pub(crate) async fn register_tls_listeners<T>(
+    server: &mut ServerFuture<T>,
+    config: &TlsConfig,
+) -> Result<(), anyhow::Error>
+where
+    T: trust_dns_server::server::RequestHandler,
+{
+    let (certs, key) = load_certificate(config)?;
+
+    if let Some(listen) = config.dot_listen {
+        let tcp = TcpListener::bind(listen).await?;
+        server.register_tls_listener(
+            tcp,
+            Duration::from_secs(30),
+            (certs.clone(), key.clone()),
+        )?;
+    }
+
+    if let Some(listen) = config.doh_listen {
+        let tcp = TcpListener::bind(listen).await?;
+        server.register_https_listener(
+            tcp,
+            Duration::from_secs(30),
+            (certs, key),
+            "dns.zeronsd".to_string(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Serves DoT/DoH for as long as the process runs: builds a `ServerFuture` over the same
+/// `ZTAuthority` the plaintext listeners and control API use, registers the configured TLS
+/// listeners on it, and blocks until they exit.
+pub(crate) async fn serve(zt: Arc<RwLock<ZTAuthority>>, config: TlsConfig) -> Result<(), anyhow::Error> {
+    let mut server = ServerFuture::new(ZtHandler::new(zt));
+    register_tls_listeners(&mut server, &config).await?;
+    server.block_until_done().await?;
+    Ok(())
+}
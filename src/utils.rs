@@ -1,7 +1,8 @@
-use std::{str::FromStr, time::Duration};
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 use regex::Regex;
 use tokio::runtime::Runtime;
+use tokio::sync::RwLock;
 use trust_dns_resolver::IntoName;
 use trust_dns_server::client::rr::Name;
 use zerotier_central_api::apis::configuration::Configuration;
@@ -11,6 +12,8 @@ use anyhow::anyhow;
 use crate::authority::Authority;
 use crate::authority::PtrAuthority;
 use crate::authority::ZTAuthority;
+use crate::config::Config;
+use crate::records::install_static_records;
 
 pub(crate) const DOMAIN_NAME: &str = "domain.";
 pub(crate) const VERSION_STRING: &str = env!("CARGO_PKG_VERSION");
@@ -43,10 +46,12 @@ pub(crate) fn parse_ip_from_cidr(ip_with_cidr: String) -> String {
         .to_string()
 }
 
-pub(crate) fn central_token(arg: Option<&str>) -> Option<String> {
-    if arg.is_some() {
+pub(crate) fn central_token(arg: Option<&str>, config: &Config) -> Option<String> {
+    let arg = config.token(arg);
+
+    if let Some(arg) = arg {
         return Some(
-            std::fs::read_to_string(arg.unwrap())
+            std::fs::read_to_string(&arg)
                 .expect("Could not load token file")
                 .trim()
                 .to_string(),
@@ -62,9 +67,9 @@ pub(crate) fn central_token(arg: Option<&str>) -> Option<String> {
     None
 }
 
-pub(crate) fn authtoken_path(arg: Option<&str>) -> String {
-    if let Some(arg) = arg {
-        return String::from(arg);
+pub(crate) fn authtoken_path(arg: Option<&str>, config: &Config) -> String {
+    if let Some(arg) = config.authtoken_path(arg) {
+        return arg;
     } else {
         if cfg!(target_os = "linux") {
             String::from("/var/lib/zerotier-one/authtoken.secret")
@@ -80,8 +85,8 @@ pub(crate) fn authtoken_path(arg: Option<&str>) -> String {
     }
 }
 
-pub(crate) fn domain_or_default(tld: Option<&str>) -> Result<Name, anyhow::Error> {
-    if let Some(tld) = tld {
+pub(crate) fn domain_or_default(tld: Option<&str>, config: &Config) -> Result<Name, anyhow::Error> {
+    if let Some(tld) = config.domain(tld) {
         if tld.len() > 0 {
             return Ok(Name::from_str(&format!("{}.", tld))?);
         } else {
@@ -170,26 +175,95 @@ pub(crate) fn update_central_dns(
     Ok(())
 }
 
+/// Default Central-to-Authority refresh interval when neither `--update-interval` nor the
+/// config file's `update_interval` is set.
+const DEFAULT_UPDATE_INTERVAL: Duration = Duration::from_secs(30);
+
 pub(crate) fn init_authority(
     ptr_authority: PtrAuthority,
     token: String,
-    network: String,
+    network: Option<String>,
     domain_name: Name,
     hosts_file: Option<String>,
-    update_interval: Duration,
-    authority: Authority,
+    update_interval: Option<Duration>,
+    mut authority: Authority,
+    config: &Config,
 ) -> ZTAuthority {
+    if let Err(e) = install_static_records(&mut authority, &domain_name, &config.dns_records) {
+        eprintln!("Failed to install static dns_records from config: {:?}", e);
+    }
+
+    let network = config
+        .network(network.as_deref())
+        .expect("network must be set via --network or the config file");
+
     ZTAuthority::new(
         domain_name.clone(),
-        network.clone(),
+        network,
         central_config(token),
-        hosts_file,
+        config.hosts_file(hosts_file.as_deref()),
         ptr_authority,
-        update_interval,
+        config.update_interval(update_interval).unwrap_or(DEFAULT_UPDATE_INTERVAL),
         authority,
     )
 }
 
+/// Wraps the `ZTAuthority` built by `init_authority` in a shared handle and spawns the
+/// hot-reload watcher plus the background services enabled in `config` on `runtime`,
+/// alongside the DNS listeners. This is
+/// the runtime startup path: anything that needs to read or mutate the live zone after
+/// startup (the control API, the hot-reload watcher, DNSSEC re-signing, DoT/DoH) gets wired up
+/// here rather than left uncalled.
+pub(crate) fn serve(
+    runtime: &Runtime,
+    zt: ZTAuthority,
+    config: &Config,
+    config_path: Option<String>,
+) -> Arc<RwLock<ZTAuthority>> {
+    let hosts_file = zt.hosts_file().map(String::from);
+    let update_interval = zt.update_interval();
+    let zt = Arc::new(RwLock::new(zt));
+
+    if let Some(api_config) = config.api.clone() {
+        let api_zt = zt.clone();
+        runtime.spawn(async move {
+            if let Err(e) = crate::api::serve(api_zt, api_config).await {
+                eprintln!("control API exited: {:?}", e);
+            }
+        });
+    }
+
+    {
+        let reload_zt = zt.clone();
+        runtime.spawn(async move {
+            if let Err(e) = crate::reload::watch_for_reload(reload_zt, hosts_file, config_path).await
+            {
+                eprintln!("hot-reload watcher exited: {:?}", e);
+            }
+        });
+    }
+
+    if let Some(dnssec_config) = config.dnssec.clone() {
+        let dnssec_zt = zt.clone();
+        runtime.spawn(crate::dnssec::run_resign_loop(
+            dnssec_zt,
+            dnssec_config,
+            update_interval,
+        ));
+    }
+
+    if let Some(tls_config) = config.tls.clone() {
+        let tls_zt = zt.clone();
+        runtime.spawn(async move {
+            if let Err(e) = crate::tls::serve(tls_zt, tls_config).await {
+                eprintln!("TLS listeners exited: {:?}", e);
+            }
+        });
+    }
+
+    zt
+}
+
 fn translation_table() -> Vec<(Regex, &'static str)> {
     vec![
         (Regex::new(r"\s+").unwrap(), "-"), // translate whitespace to `-`